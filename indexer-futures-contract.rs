@@ -12,11 +12,98 @@ use stylus_sdk::{
 
 // Event definitions
 sol! {
-    event FutureCreated(address indexed indexer, address indexed buyer, uint256 amount, uint256 duration);
+    event FutureCreated(address indexed indexer, address indexed buyer, uint256 amount, uint256 duration, uint256 price);
     event FutureCancelled(address indexed indexer, address indexed buyer, uint256 amount);
     event FutureSettled(address indexed indexer, address indexed buyer, uint256 amount);
     event IndexerStaked(address indexed indexer, uint256 amount);
     event POISubmitted(bytes32 indexed subgraphId, uint256 blockNumber, bytes32 poi);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event Paused(address indexed account);
+    event Unpaused(address indexed account);
+    event FutureSlashed(address indexed indexer, address indexed buyer, uint256 slashed_amount);
+    event OwnershipTransferStarted(address indexed previous_owner, address indexed new_owner);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event ConfigUpdated(string field, address old_value, address new_value);
+}
+
+/// `DEFAULT_ADMIN_ROLE` is the zero role, mirroring OpenZeppelin's AccessControl convention.
+/// Admins of a role may grant or revoke that role; the deployer holds `DEFAULT_ADMIN_ROLE`.
+pub const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+
+/// Role allowed to call `submit_poi`, enrolled by the admin for trusted Graphcast oracle relayers.
+/// `keccak256("POI_SUBMITTER_ROLE")`, matching the OpenZeppelin AccessControl convention of
+/// hashing role names rather than packing them as raw bytes.
+pub const POI_SUBMITTER_ROLE: B256 = B256::new([
+    0xdc, 0x39, 0x74, 0x93, 0xf0, 0xad, 0xa9, 0xa9, 0x25, 0xfa, 0x29, 0x3f, 0xb6, 0x25, 0x16, 0x24,
+    0x86, 0xc8, 0xe8, 0xff, 0x49, 0x73, 0x29, 0x53, 0x0b, 0xdc, 0xfe, 0xaa, 0xdf, 0xab, 0x39, 0x5d,
+]);
+
+/// Default `max_poi_age`, in seconds, applied at deploy so performance checks don't fail by
+/// default before the owner has a chance to call `set_max_poi_age`: one week.
+pub const DEFAULT_MAX_POI_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Default `slash_bps` applied at deploy: 10%, out of 10_000.
+pub const DEFAULT_SLASH_BPS: u64 = 1_000;
+
+/// A pricing model that derives a GRT cost from how much capacity an indexer has
+/// already committed (`sold`) and how much more is being requested (`amount`).
+pub trait CurveFunction {
+    fn price(&self, sold: U256, amount: U256) -> Result<U256, Vec<u8>>;
+}
+
+/// Linear bonding curve: `cost = amount * initial_price + linear_coefficient * (sold + amount/2) * amount`.
+/// The `(sold + amount/2)` term integrates the linear slope over the `[sold, sold + amount]`
+/// interval being purchased, so later buyers pay more as committed capacity fills up.
+pub struct LinearFunction {
+    pub initial_price: U256,
+    pub linear_coefficient: U256,
+}
+
+impl CurveFunction for LinearFunction {
+    fn price(&self, sold: U256, amount: U256) -> Result<U256, Vec<u8>> {
+        if self.initial_price.is_zero() || self.linear_coefficient.is_zero() {
+            return Err("Bonding curve: initial_price and linear_coefficient must be set".into());
+        }
+
+        let base_cost = amount
+            .checked_mul(self.initial_price)
+            .ok_or_else(|| "Bonding curve: overflow computing base cost".to_string().into_bytes())?;
+
+        let midpoint = sold
+            .checked_add(amount / U256::from(2))
+            .ok_or_else(|| "Bonding curve: overflow computing midpoint".to_string().into_bytes())?;
+
+        let slope_cost = self
+            .linear_coefficient
+            .checked_mul(midpoint)
+            .and_then(|v| v.checked_mul(amount))
+            .ok_or_else(|| "Bonding curve: overflow computing slope cost".to_string().into_bytes())?;
+
+        base_cost
+            .checked_add(slope_cost)
+            .ok_or_else(|| "Bonding curve: overflow computing total cost".to_string().into_bytes())
+    }
+}
+
+/// The portion of `stake` slashed when a matured future fails `check_indexer_performance`,
+/// given `slash_bps` out of 10_000.
+fn compute_slash_amount(stake: U256, slash_bps: U256) -> U256 {
+    stake * slash_bps / U256::from(10_000)
+}
+
+/// Per-indexer bonding curve parameters backing `LinearFunction`
+#[solidity_storage]
+pub struct CurveParams {
+    initial_price: StorageUint,
+    linear_coefficient: StorageUint,
+}
+
+/// A submitted Proof of Indexing, with the timestamp it was recorded at
+#[solidity_storage]
+pub struct POIRecord {
+    poi: StorageBytes32,
+    submitted_at: StorageUint,
 }
 
 /// Main storage structure for the Indexer Futures Contract
@@ -28,7 +115,19 @@ pub struct IndexerFuturesContract {
     grt_token: StorageAddress,
     graph_token: StorageAddress,
     staking_contract: StorageAddress,
-    poi_data: StorageMap<(B256, U256), StorageBytes32>, // (subgraphId, blockNumber) => POI
+    poi_data: StorageMap<(B256, U256), POIRecord>, // (subgraphId, blockNumber) => POI
+    last_poi_submitted_at: StorageMap<Address, StorageUint>, // indexer => timestamp of their latest POI
+    roles: StorageMap<(B256, Address), StorageBool>, // (role, account) => has role
+    paused: StorageBool,
+    active_future_keys: StorageVec<(Address, Address)>,
+    active_future_index: StorageMap<(Address, Address), StorageUint>, // key => 1-based index into active_future_keys, 0 = absent
+    operation_cursor: StorageUint, // resume point for settle_matured_batch
+    curve_params: StorageMap<Address, CurveParams>,
+    committed_amount: StorageMap<Address, StorageUint>, // indexer => sum of amount across its active futures
+    min_stake: StorageUint,
+    max_poi_age: StorageUint,
+    slash_bps: StorageUint, // out of 10_000
+    pending_owner: StorageAddress,
 }
 
 /// Represents a single future contract between an indexer and a buyer
@@ -37,6 +136,7 @@ pub struct Future {
     indexer: StorageAddress,
     buyer: StorageAddress,
     amount: StorageUint,
+    price: StorageUint, // GRT escrowed, as derived from the indexer's bonding curve at creation
     start_time: StorageUint,
     duration: StorageUint,
     is_active: StorageBool,
@@ -46,14 +146,176 @@ pub struct Future {
 impl IndexerFuturesContract {
     /// Initializes the contract with necessary addresses
     pub fn constructor(&mut self, grt_token: Address, graph_token: Address, staking_contract: Address) {
-        self.owner.set(msg::sender());
+        let deployer = msg::sender();
+        self.owner.set(deployer);
         self.grt_token.set(grt_token);
         self.graph_token.set(graph_token);
         self.staking_contract.set(staking_contract);
+
+        self.roles.insert((DEFAULT_ADMIN_ROLE, deployer), StorageBool::new(true));
+        evm::log(RoleGranted { role: DEFAULT_ADMIN_ROLE, account: deployer, sender: deployer });
+
+        // Sane defaults so settlement doesn't silently refund every buyer (and slash nothing)
+        // just because the owner hasn't called set_max_poi_age/set_slash_bps yet.
+        self.max_poi_age.set(U256::from(DEFAULT_MAX_POI_AGE_SECS));
+        self.slash_bps.set(U256::from(DEFAULT_SLASH_BPS));
+    }
+
+    /// Returns whether `account` holds `role`
+    pub fn has_role(&self, role: B256, account: Address) -> Result<bool, Vec<u8>> {
+        Ok(self.roles.get(&(role, account)).get())
+    }
+
+    /// Grants `role` to `account`. Restricted to `DEFAULT_ADMIN_ROLE` holders.
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), Vec<u8>> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+
+        let sender = msg::sender();
+        self.roles.insert((role, account), StorageBool::new(true));
+        evm::log(RoleGranted { role, account, sender });
+        Ok(())
+    }
+
+    /// Revokes `role` from `account`. Restricted to `DEFAULT_ADMIN_ROLE` holders.
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), Vec<u8>> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+
+        let sender = msg::sender();
+        self.roles.insert((role, account), StorageBool::new(false));
+        evm::log(RoleRevoked { role, account, sender });
+        Ok(())
+    }
+
+    /// Allows the caller to renounce a role held by themselves
+    pub fn renounce_role(&mut self, role: B256) -> Result<(), Vec<u8>> {
+        let sender = msg::sender();
+        self.roles.insert((role, sender), StorageBool::new(false));
+        evm::log(RoleRevoked { role, account: sender, sender });
+        Ok(())
+    }
+
+    /// Pauses state-changing endpoints. Restricted to `owner`. `cancel_future` stays
+    /// reachable while paused so buyers can always reclaim escrowed GRT.
+    pub fn pause(&mut self) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        self.paused.set(true);
+        evm::log(Paused { account: msg::sender() });
+        Ok(())
+    }
+
+    /// Resumes state-changing endpoints. Restricted to `owner`.
+    pub fn unpause(&mut self) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        self.paused.set(false);
+        evm::log(Unpaused { account: msg::sender() });
+        Ok(())
+    }
+
+    /// Sets the minimum stake (queried from `staking_contract`) an indexer must hold to pass
+    /// `check_indexer_performance`. Restricted to `owner`.
+    pub fn set_min_stake(&mut self, min_stake: U256) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        self.min_stake.set(min_stake);
+        Ok(())
+    }
+
+    /// Sets how old an indexer's most recent POI may be before it's considered stale.
+    /// Restricted to `owner`.
+    pub fn set_max_poi_age(&mut self, max_poi_age: U256) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        self.max_poi_age.set(max_poi_age);
+        Ok(())
+    }
+
+    /// Sets the fraction (in basis points, out of 10_000) of an indexer's stake slashed when
+    /// settlement is attempted but performance fails. Restricted to `owner`.
+    pub fn set_slash_bps(&mut self, slash_bps: U256) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        if slash_bps > U256::from(10_000) {
+            return Err("Slash fraction cannot exceed 10000 bps".into());
+        }
+        self.slash_bps.set(slash_bps);
+        Ok(())
+    }
+
+    /// Begins a two-step ownership transfer to `new_owner`. Restricted to `owner`; has no
+    /// effect until `new_owner` calls `accept_ownership`, so a mistyped address can't
+    /// permanently brick administration of the custodied funds.
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err("New owner cannot be the zero address".into());
+        }
+        self.pending_owner.set(new_owner);
+        evm::log(OwnershipTransferStarted { previous_owner: self.owner.get(), new_owner });
+        Ok(())
+    }
+
+    /// Completes a pending ownership transfer. Callable only by the stored `pending_owner`.
+    pub fn accept_ownership(&mut self) -> Result<(), Vec<u8>> {
+        let new_owner = msg::sender();
+        if new_owner != self.pending_owner.get() {
+            return Err("Caller is not the pending owner".into());
+        }
+
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        self.pending_owner.set(Address::ZERO);
+        evm::log(OwnershipTransferred { previous_owner, new_owner });
+
+        // `owner` and `DEFAULT_ADMIN_ROLE` are separate permission systems; migrate the
+        // latter too so a compromised previous owner can't keep granting/revoking roles
+        // (e.g. de-enrolling the Graphcast oracle or enrolling itself to forge POIs).
+        self.roles.insert((DEFAULT_ADMIN_ROLE, previous_owner), StorageBool::new(false));
+        evm::log(RoleRevoked { role: DEFAULT_ADMIN_ROLE, account: previous_owner, sender: new_owner });
+
+        self.roles.insert((DEFAULT_ADMIN_ROLE, new_owner), StorageBool::new(true));
+        evm::log(RoleGranted { role: DEFAULT_ADMIN_ROLE, account: new_owner, sender: new_owner });
+
+        Ok(())
+    }
+
+    /// Rotates the staking contract address, e.g. if The Graph redeploys infrastructure.
+    /// Restricted to `owner`.
+    pub fn set_staking_contract(&mut self, new_staking_contract: Address) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        if new_staking_contract == Address::ZERO {
+            return Err("New staking contract cannot be the zero address".into());
+        }
+        let old_value = self.staking_contract.get();
+        self.staking_contract.set(new_staking_contract);
+        evm::log(ConfigUpdated { field: "staking_contract".into(), old_value, new_value: new_staking_contract });
+        Ok(())
+    }
+
+    /// Rotates the GRT token address. Restricted to `owner`.
+    pub fn set_grt_token(&mut self, new_grt_token: Address) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        if new_grt_token == Address::ZERO {
+            return Err("New GRT token cannot be the zero address".into());
+        }
+        let old_value = self.grt_token.get();
+        self.grt_token.set(new_grt_token);
+        evm::log(ConfigUpdated { field: "grt_token".into(), old_value, new_value: new_grt_token });
+        Ok(())
+    }
+
+    /// Rotates the Graph token address. Restricted to `owner`.
+    pub fn set_graph_token(&mut self, new_graph_token: Address) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+        if new_graph_token == Address::ZERO {
+            return Err("New Graph token cannot be the zero address".into());
+        }
+        let old_value = self.graph_token.get();
+        self.graph_token.set(new_graph_token);
+        evm::log(ConfigUpdated { field: "graph_token".into(), old_value, new_value: new_graph_token });
+        Ok(())
     }
 
     /// Allows an indexer to stake GRT tokens
     pub fn stake(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        self.when_not_paused()?;
+
         let indexer = msg::sender();
         let current_stake = self.indexer_stakes.get(&indexer).get();
         self.indexer_stakes.insert(indexer, current_stake + amount);
@@ -67,6 +329,8 @@ impl IndexerFuturesContract {
 
     /// Creates a new future contract between an indexer and a buyer
     pub fn create_future(&mut self, buyer: Address, amount: U256, duration: U256) -> Result<(), Vec<u8>> {
+        self.when_not_paused()?;
+
         let indexer = msg::sender();
         let key = (indexer, buyer);
 
@@ -79,21 +343,44 @@ impl IndexerFuturesContract {
             return Err("Insufficient stake".into());
         }
 
+        let price = self.price_future(indexer, amount)?;
+        let sold = self.committed_amount.get(&indexer).get();
+        self.committed_amount.insert(indexer, sold + amount);
+
         let future = Future {
             indexer: StorageAddress::new(indexer),
             buyer: StorageAddress::new(buyer),
             amount: StorageUint::new(amount),
+            price: StorageUint::new(price),
             start_time: StorageUint::new(block::timestamp()),
             duration: StorageUint::new(duration),
             is_active: StorageBool::new(true),
         };
 
         self.futures.insert(key, future);
+        self.track_active_key(key);
 
         // Transfer GRT tokens from buyer to this contract
-        self.transfer_grt(buyer, Address::from(self), amount)?;
+        self.transfer_grt(buyer, Address::from(self), price)?;
 
-        evm::log(FutureCreated { indexer, buyer, amount, duration });
+        evm::log(FutureCreated { indexer, buyer, amount, duration, price });
+        Ok(())
+    }
+
+    /// Sets the indexer's own bonding curve parameters used to price future `create_future` calls
+    pub fn set_curve_params(&mut self, initial_price: U256, linear_coefficient: U256) -> Result<(), Vec<u8>> {
+        if initial_price.is_zero() || linear_coefficient.is_zero() {
+            return Err("Bonding curve: initial_price and linear_coefficient must be non-zero".into());
+        }
+
+        let indexer = msg::sender();
+        self.curve_params.insert(
+            indexer,
+            CurveParams {
+                initial_price: StorageUint::new(initial_price),
+                linear_coefficient: StorageUint::new(linear_coefficient),
+            },
+        );
         Ok(())
     }
 
@@ -101,7 +388,7 @@ impl IndexerFuturesContract {
     pub fn cancel_future(&mut self, indexer: Address) -> Result<(), Vec<u8>> {
         let buyer = msg::sender();
         let key = (indexer, buyer);
-        
+
         let mut future = self.futures.get(&key);
         if !future.is_active.get() {
             return Err("No active future found".into());
@@ -109,8 +396,10 @@ impl IndexerFuturesContract {
 
         future.is_active.set(false);
         self.futures.insert(key, future);
+        self.untrack_active_key(key);
+        self.release_committed_amount(indexer, future.amount.get());
 
-        let amount = future.amount.get();
+        let amount = future.price.get();
         evm::log(FutureCancelled { indexer, buyer, amount });
 
         // Transfer GRT tokens back to the buyer
@@ -119,11 +408,106 @@ impl IndexerFuturesContract {
         Ok(())
     }
 
+    /// Walks active futures starting from the persisted `operation_cursor`, examining up to
+    /// `max_steps` entries per call (each entry counts as a step whether or not it has matured)
+    /// and settling the matured ones. Returns `true` once the full set has been swept
+    /// (and resets the cursor) or `false` if the call ran out of steps and more remain; the
+    /// cursor is persisted either way so a later call resumes exactly where this one stopped.
+    pub fn settle_matured_batch(&mut self, max_steps: U256) -> Result<bool, Vec<u8>> {
+        self.when_not_paused()?;
+
+        let mut cursor = self.operation_cursor.get();
+        let mut steps = U256::ZERO;
+
+        while steps < max_steps {
+            let len = U256::from(self.active_future_keys.len() as u64);
+            if cursor >= len {
+                self.operation_cursor.set(U256::ZERO);
+                return Ok(true);
+            }
+
+            let idx = cursor.to::<u64>() as usize;
+            let key = match self.active_future_keys.get(idx) {
+                Some(key) => key,
+                None => break,
+            };
+            let (indexer, buyer) = key;
+            let mut future = self.futures.get(&key);
+
+            let end_time = future.start_time.get() + future.duration.get();
+            if block::timestamp() < end_time {
+                // Not matured yet: leave it active and move on to the next entry.
+                cursor += U256::from(1);
+                steps += U256::from(1);
+                continue;
+            }
+
+            let committed = future.amount.get();
+            let escrowed = future.price.get();
+
+            // Flip the future inactive and untrack it before check_indexer_performance's
+            // staking query (or the settlement/refund transfer) runs, so a reentrant call
+            // back into this contract can't observe it as still active. If the external
+            // calls below end up failing, the future is re-activated and re-tracked so the
+            // existing per-future retry semantics are preserved.
+            future.is_active.set(false);
+            self.futures.insert(key, future);
+            // Swap-remove backfills `idx` with the former last element, so the cursor
+            // stays put and re-examines that same slot on the next step.
+            self.untrack_active_key(key);
+            self.release_committed_amount(indexer, committed);
+
+            // A single future's external calls (the staking-contract performance query, or
+            // the GRT transfer) failing must not revert the whole batch and lose progress
+            // already made on unrelated futures. Leave the future active and advance past
+            // it on error so a later call can retry it once the underlying issue clears.
+            let settled = match self.check_indexer_performance(indexer) {
+                Ok(true) => self
+                    .transfer_grt(Address::from(self), indexer, escrowed)
+                    .map(|()| evm::log(FutureSettled { indexer, buyer, amount: escrowed })),
+                Ok(false) => self.slash_and_refund(indexer, buyer, escrowed),
+                Err(e) => Err(e),
+            };
+
+            if settled.is_err() {
+                let mut future = self.futures.get(&key);
+                future.is_active.set(true);
+                self.futures.insert(key, future);
+                self.track_active_key(key);
+                // track_active_key appended the key; swap it back into slot `idx`, which the
+                // swap-remove above backfilled, so advancing the cursor doesn't skip that entry.
+                let last_idx = self.active_future_keys.len() - 1;
+                if idx != last_idx {
+                    if let Some(backfilled) = self.active_future_keys.get(idx) {
+                        self.active_future_keys.set(idx, key);
+                        self.active_future_keys.set(last_idx, backfilled);
+                        self.active_future_index.insert(key, StorageUint::new(U256::from((idx + 1) as u64)));
+                        self.active_future_index.insert(backfilled, StorageUint::new(U256::from((last_idx + 1) as u64)));
+                    }
+                }
+                let recommitted = self.committed_amount.get(&indexer).get();
+                self.committed_amount.insert(indexer, recommitted + committed);
+                cursor += U256::from(1);
+            }
+            steps += U256::from(1);
+        }
+
+        if cursor >= U256::from(self.active_future_keys.len() as u64) {
+            self.operation_cursor.set(U256::ZERO);
+            return Ok(true);
+        }
+
+        self.operation_cursor.set(cursor);
+        Ok(false)
+    }
+
     /// Allows an indexer to settle a matured future contract
     pub fn settle_future(&mut self, buyer: Address) -> Result<(), Vec<u8>> {
+        self.when_not_paused()?;
+
         let indexer = msg::sender();
         let key = (indexer, buyer);
-        
+
         let mut future = self.futures.get(&key);
         if !future.is_active.get() {
             return Err("No active future found".into());
@@ -135,45 +519,155 @@ impl IndexerFuturesContract {
             return Err("Future has not yet matured".into());
         }
 
-        // Check indexer's performance using The Graph's mechanisms (simplified here)
-        if !self.check_indexer_performance(indexer)? {
-            return Err("Indexer performance does not meet requirements".into());
-        }
+        let committed = future.amount.get();
+        let escrowed = future.price.get();
 
+        // Flip the future inactive and untrack it before making any external call
+        // (check_indexer_performance's staking query, then the settlement/refund
+        // transfer) so a reentrant call back into this contract can't observe it as
+        // still active.
         future.is_active.set(false);
         self.futures.insert(key, future);
+        self.untrack_active_key(key);
+        self.release_committed_amount(indexer, committed);
 
-        let amount = future.amount.get();
-        evm::log(FutureSettled { indexer, buyer, amount });
+        let performed = self.check_indexer_performance(indexer)?;
 
-        // Transfer GRT tokens to the indexer
-        self.transfer_grt(Address::from(self), indexer, amount)?;
+        if performed {
+            evm::log(FutureSettled { indexer, buyer, amount: escrowed });
+            // Transfer GRT tokens to the indexer
+            self.transfer_grt(Address::from(self), indexer, escrowed)?;
+        } else {
+            // Performance requirements weren't met: slash the indexer's stake and refund
+            // the buyer's escrowed GRT instead of erroring and leaving it locked.
+            self.slash_and_refund(indexer, buyer, escrowed)?;
+        }
 
         Ok(())
     }
 
-    /// Submits a Proof of Indexing (POI) for a specific subgraph and block
-    /// Note: This is a placeholder and should be replaced with Graphcast integration in the future
-    pub fn submit_poi(&mut self, subgraph_id: B256, block_number: U256, poi: B256) -> Result<(), Vec<u8>> {
-        // In a real implementation, this should be restricted to authorized parties or use Graphcast
+    /// Submits a Proof of Indexing (POI) on behalf of `indexer` for a specific subgraph and block
+    /// Note: Restricted to enrolled Graphcast oracle relayers via `POI_SUBMITTER_ROLE`
+    pub fn submit_poi(&mut self, indexer: Address, subgraph_id: B256, block_number: U256, poi: B256) -> Result<(), Vec<u8>> {
+        self.only_role(POI_SUBMITTER_ROLE)?;
+
         let key = (subgraph_id, block_number);
-        self.poi_data.insert(key, StorageBytes32::new(poi));
+        let submitted_at = block::timestamp();
+        self.poi_data.insert(key, POIRecord { poi: StorageBytes32::new(poi), submitted_at: StorageUint::new(submitted_at) });
+        self.last_poi_submitted_at.insert(indexer, submitted_at);
 
         evm::log(POISubmitted { subgraphId: subgraph_id, blockNumber: block_number, poi });
         Ok(())
     }
 
-    /// Checks the performance of an indexer
-    /// Note: This is a simplified placeholder and should be expanded in future versions
+    /// Checks the performance of an indexer: they must have submitted a POI within
+    /// `max_poi_age` and hold at least `min_stake` in the external staking contract
     fn check_indexer_performance(&self, indexer: Address) -> Result<bool, Vec<u8>> {
-        // This is a simplified placeholder. In a real implementation, this would involve:
-        // 1. Checking the indexer's stake in the Graph's staking contract
-        // 2. Verifying recent POIs submitted by the indexer
-        // 3. Potentially querying the Graph's network subgraph for more detailed metrics
-        
-        // For now, we'll just check if the indexer has any stake
+        let last_poi = self.last_poi_submitted_at.get(&indexer).get();
+        if last_poi.is_zero() {
+            return Ok(false);
+        }
+
+        let poi_age = block::timestamp().saturating_sub(last_poi);
+        if poi_age > self.max_poi_age.get() {
+            return Ok(false);
+        }
+
+        let staked = call::indexer_staked_tokens(self.staking_contract.get(), indexer)?;
+        Ok(staked >= self.min_stake.get())
+    }
+
+    /// Reverts unless `msg::sender()` holds `role`
+    fn only_role(&self, role: B256) -> Result<(), Vec<u8>> {
+        if !self.roles.get(&(role, msg::sender())).get() {
+            return Err("AccessControl: account is missing role".into());
+        }
+        Ok(())
+    }
+
+    /// Prices a new `amount` of committed capacity off `indexer`'s bonding curve, against
+    /// whatever they've already sold
+    fn price_future(&self, indexer: Address, amount: U256) -> Result<U256, Vec<u8>> {
+        let params = self.curve_params.get(&indexer);
+        let curve = LinearFunction {
+            initial_price: params.initial_price.get(),
+            linear_coefficient: params.linear_coefficient.get(),
+        };
+        let sold = self.committed_amount.get(&indexer).get();
+        curve.price(sold, amount)
+    }
+
+    /// Frees `amount` of capacity back onto `indexer`'s bonding curve once a future deactivates
+    fn release_committed_amount(&mut self, indexer: Address, amount: U256) {
+        let committed = self.committed_amount.get(&indexer).get();
+        self.committed_amount.insert(indexer, committed.saturating_sub(amount));
+    }
+
+    /// Slashes `slash_bps` of `indexer`'s recorded stake, refunds the buyer's escrowed GRT
+    /// instead of paying the indexer, and emits `FutureSlashed`. Called when a matured future
+    /// fails `check_indexer_performance` at settlement time.
+    fn slash_and_refund(&mut self, indexer: Address, buyer: Address, refund_amount: U256) -> Result<(), Vec<u8>> {
+        // Attempt the refund before touching any state: in settle_matured_batch an Err here
+        // is caught rather than propagated, so mutating indexer_stakes first would leave the
+        // indexer slashed (repeatedly, on every retry) even though the buyer was never paid.
+        self.transfer_grt(Address::from(self), buyer, refund_amount)?;
+
         let stake = self.indexer_stakes.get(&indexer).get();
-        Ok(stake > U256::ZERO)
+        let slashed_amount = compute_slash_amount(stake, self.slash_bps.get());
+        self.indexer_stakes.insert(indexer, stake - slashed_amount);
+
+        evm::log(FutureSlashed { indexer, buyer, slashed_amount });
+        Ok(())
+    }
+
+    /// Reverts unless `msg::sender()` is `owner`
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err("Caller is not the owner".into());
+        }
+        Ok(())
+    }
+
+    /// Reverts if the contract is paused
+    fn when_not_paused(&self) -> Result<(), Vec<u8>> {
+        if self.paused.get() {
+            return Err("Pausable: contract is paused".into());
+        }
+        Ok(())
+    }
+
+    /// Appends `key` to the iterable set of active futures used by `settle_matured_batch`
+    fn track_active_key(&mut self, key: (Address, Address)) {
+        self.active_future_keys.push(key);
+        let idx = self.active_future_keys.len() - 1;
+        self.active_future_index.insert(key, StorageUint::new(U256::from((idx + 1) as u64)));
+    }
+
+    /// Removes `key` from the iterable set of active futures via swap-remove, keeping the
+    /// index map consistent and nudging `operation_cursor` back if the removed slot sat
+    /// ahead of it, so an in-flight `settle_matured_batch` neither skips nor double-settles.
+    fn untrack_active_key(&mut self, key: (Address, Address)) {
+        let idx_plus_one = self.active_future_index.get(&key).get();
+        if idx_plus_one == U256::ZERO {
+            return;
+        }
+        let idx = (idx_plus_one.to::<u64>() - 1) as usize;
+        let last_idx = self.active_future_keys.len() - 1;
+
+        if idx != last_idx {
+            if let Some(last_key) = self.active_future_keys.get(last_idx) {
+                self.active_future_keys.set(idx, last_key);
+                self.active_future_index.insert(last_key, StorageUint::new(U256::from((idx + 1) as u64)));
+            }
+        }
+        self.active_future_keys.pop();
+        self.active_future_index.insert(key, StorageUint::new(U256::ZERO));
+
+        let cursor = self.operation_cursor.get();
+        let removed_idx = U256::from(idx as u64);
+        if removed_idx < cursor {
+            self.operation_cursor.set(cursor - U256::from(1));
+        }
     }
 
     /// Helper function to transfer GRT tokens
@@ -201,6 +695,12 @@ mod call {
         fn transferFrom(from: Address, to: Address, amount: U256) -> bool;
     }
 
+    /// The Graph's staking contract, queried to check an indexer's performance
+    #[solidity_abi]
+    pub trait IStaking {
+        fn getIndexerStakedTokens(indexer: Address) -> U256;
+    }
+
     pub fn transfer_tokens(token: Address, from: Address, to: Address, amount: U256) -> Result<bool, Vec<u8>> {
         if from == Address::from(0) {
             ERC20::transfer(token, to, amount)
@@ -208,4 +708,89 @@ mod call {
             ERC20::transferFrom(token, from, to, amount)
         }
     }
+
+    pub fn indexer_staked_tokens(staking_contract: Address, indexer: Address) -> Result<U256, Vec<u8>> {
+        IStaking::getIndexerStakedTokens(staking_contract, indexer)
+            .map_err(|_| "Staking query failed".to_string().into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    #[test]
+    fn untrack_active_key_swap_removes_and_rewinds_cursor_past_it() {
+        let vm = TestVM::default();
+        let mut contract = IndexerFuturesContract::from(vm);
+
+        let indexer = Address::from([0x11; 20]);
+        let keys = [
+            (indexer, Address::from([0x01; 20])),
+            (indexer, Address::from([0x02; 20])),
+            (indexer, Address::from([0x03; 20])),
+        ];
+        for key in keys {
+            contract.track_active_key(key);
+        }
+
+        // Cursor sits past the entry we're about to remove (index 1), so removing it
+        // must rewind the cursor by one or a resumed settle_matured_batch would skip
+        // the future that swap-remove shifts into the freed slot.
+        contract.operation_cursor.set(U256::from(2));
+        contract.untrack_active_key(keys[1]);
+
+        assert_eq!(contract.active_future_keys.len(), 2);
+        assert_eq!(contract.operation_cursor.get(), U256::from(1));
+        assert_eq!(contract.active_future_keys.get(0).unwrap(), keys[0]);
+        assert_eq!(contract.active_future_keys.get(1).unwrap(), keys[2]);
+        assert_eq!(contract.active_future_index.get(&keys[2]).get(), U256::from(2));
+        assert_eq!(contract.active_future_index.get(&keys[1]).get(), U256::ZERO);
+    }
+
+    #[test]
+    fn untrack_active_key_leaves_cursor_when_removed_entry_is_ahead_of_it() {
+        let vm = TestVM::default();
+        let mut contract = IndexerFuturesContract::from(vm);
+
+        let indexer = Address::from([0x11; 20]);
+        let keys = [
+            (indexer, Address::from([0x01; 20])),
+            (indexer, Address::from([0x02; 20])),
+            (indexer, Address::from([0x03; 20])),
+        ];
+        for key in keys {
+            contract.track_active_key(key);
+        }
+
+        // Cursor (0) is behind the removed index (2), so nothing the cursor already
+        // passed shifted and it should stay put.
+        contract.operation_cursor.set(U256::from(0));
+        contract.untrack_active_key(keys[2]);
+
+        assert_eq!(contract.active_future_keys.len(), 2);
+        assert_eq!(contract.operation_cursor.get(), U256::ZERO);
+    }
+
+    #[test]
+    fn slash_amount_is_proportional_to_bps() {
+        assert_eq!(compute_slash_amount(U256::from(1_000), U256::from(1_000)), U256::from(100));
+        assert_eq!(compute_slash_amount(U256::from(1_000), U256::from(10_000)), U256::from(1_000));
+        assert_eq!(compute_slash_amount(U256::from(1_000), U256::ZERO), U256::ZERO);
+    }
+
+    #[test]
+    fn slash_amount_rounds_down() {
+        // 3 * 1 / 10_000 truncates toward zero rather than over-slashing
+        assert_eq!(compute_slash_amount(U256::from(3), U256::from(1)), U256::ZERO);
+    }
+
+    #[test]
+    fn linear_function_price_matches_formula() {
+        // cost = amount * initial_price + linear_coefficient * (sold + amount/2) * amount
+        //      = 10 * 5 + 1 * (0 + 5) * 10 = 50 + 50 = 100
+        let curve = LinearFunction { initial_price: U256::from(5), linear_coefficient: U256::from(1) };
+        assert_eq!(curve.price(U256::ZERO, U256::from(10)).unwrap(), U256::from(100));
+    }
 }